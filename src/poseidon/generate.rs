@@ -0,0 +1,179 @@
+use super::grain_lfsr::PoseidonGrainLfsr;
+use super::PoseidonParameters;
+use ark_ff::PrimeField;
+use ark_std::vec;
+use ark_std::vec::Vec;
+
+/// Derives a ready-to-use [`PoseidonParameters`] at runtime, for fields and
+/// rate/capacity combinations that don't have a hand-curated
+/// [`PoseidonDefaultParameters`](crate::poseidon::PoseidonDefaultParameters) table.
+///
+/// Round constants are drawn from a [`PoseidonGrainLfsr`] seeded with the field's
+/// characteristic bit length, the S-box exponent, the state width, and the round
+/// counts, exactly as in the Poseidon reference implementation. The MDS matrix is a
+/// Cauchy matrix over `2 * (rate + capacity)` deterministic, distinct field elements,
+/// which is unconditionally invertible (and hence MDS); candidates that additionally
+/// admit an infinitely extendable subspace trail through the partial rounds are
+/// rejected, bumping the starting index of the evaluation points until a secure
+/// candidate is found.
+pub fn generate_parameters<F: PrimeField>(
+    rate: usize,
+    capacity: usize,
+    alpha: u64,
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> PoseidonParameters<F> {
+    let t = rate + capacity;
+
+    let mut lfsr = PoseidonGrainLfsr::new(
+        false,
+        F::size_in_bits() as u64,
+        t as u64,
+        full_rounds as u64,
+        partial_rounds as u64,
+        alpha,
+    );
+    let ark = (0..(full_rounds + partial_rounds))
+        .map(|_| lfsr.get_field_elements_rejection_sampling(t))
+        .collect();
+
+    let mds = secure_cauchy_mds(t);
+
+    PoseidonParameters::new(full_rounds, partial_rounds, alpha, mds, ark, rate, capacity)
+}
+
+/// Builds the Cauchy matrix over `{0, 1, .., t-1} x {t, t+1, .., 2t-1}` shifted by
+/// `start`, i.e. `M[i][j] = 1 / ((start + i) - (start + t + j))`, re-trying with the
+/// next `start` whenever the resulting matrix is insecure.
+fn secure_cauchy_mds<F: PrimeField>(t: usize) -> Vec<Vec<F>> {
+    let mut start = 0u64;
+    loop {
+        let xs: Vec<F> = (0..t as u64).map(|i| F::from(start + i)).collect();
+        let ys: Vec<F> = (0..t as u64).map(|i| F::from(start + t as u64 + i)).collect();
+
+        let mds: Vec<Vec<F>> = xs
+            .iter()
+            .map(|x| {
+                ys.iter()
+                    .map(|y| (*x - *y).inverse().expect("x and y are always distinct"))
+                    .collect()
+            })
+            .collect();
+
+        if !admits_subspace_trail(&mds) {
+            return mds;
+        }
+        start += t as u64;
+    }
+}
+
+/// Mirrors the "secure MDS index" check from the Poseidon reference implementation.
+///
+/// The only coordinates left untouched by a single partial round's S-box are
+/// `state[1..t]`, so a necessary condition for `M` to resist an infinitely extendable
+/// subspace trail through the partial rounds is that the Krylov sequence
+/// `e, Me, M^2 e, .., M^{t-1} e` (for `e` the last standard basis vector, the
+/// lowest-degree vector supported on those coordinates) spans the full space, i.e. the
+/// minimal polynomial of `M` applied to `e` has degree `t`. If it doesn't, `M`
+/// stabilizes a low-dimensional subspace that an attacker can track across arbitrarily
+/// many partial rounds.
+fn admits_subspace_trail<F: PrimeField>(mds: &[Vec<F>]) -> bool {
+    let t = mds.len();
+    let mut v = vec![F::zero(); t];
+    v[t - 1] = F::one();
+
+    let mut krylov = Vec::with_capacity(t);
+    for _ in 0..t {
+        krylov.push(v.clone());
+        v = mat_vec_mul(mds, &v);
+    }
+
+    !rows_are_linearly_independent(krylov)
+}
+
+fn mat_vec_mul<F: PrimeField>(m: &[Vec<F>], v: &[F]) -> Vec<F> {
+    m.iter()
+        .map(|row| {
+            row.iter()
+                .zip(v)
+                .fold(F::zero(), |acc, (m_ij, v_j)| acc + *m_ij * *v_j)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_parameters;
+    use crate::poseidon::test::TestFr;
+    use crate::{CryptographicSponge, FieldBasedCryptographicSponge};
+
+    #[test]
+    fn test_generated_parameters_round_trip_through_a_sponge() {
+        use crate::poseidon::PoseidonSponge;
+
+        let params = generate_parameters::<TestFr>(2, 1, 5, 8, 31);
+        assert_eq!(params.mds.len(), 3);
+        assert_eq!(params.ark.len(), 8 + 31);
+
+        let input = vec![TestFr::from(1u8), TestFr::from(2u8)];
+        let mut sponge = PoseidonSponge::<TestFr>::new(&params);
+        sponge.absorb(&input);
+        let first = sponge.squeeze_native_field_elements(2);
+
+        // Regenerating from the same arguments is deterministic and round-trips again.
+        let params_again = generate_parameters::<TestFr>(2, 1, 5, 8, 31);
+        let mut sponge_again = PoseidonSponge::<TestFr>::new(&params_again);
+        sponge_again.absorb(&input);
+        let second = sponge_again.squeeze_native_field_elements(2);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_different_alpha_yields_different_round_constants() {
+        let alpha_3 = generate_parameters::<TestFr>(2, 1, 3, 8, 31);
+        let alpha_17 = generate_parameters::<TestFr>(2, 1, 17, 8, 31);
+
+        assert_ne!(alpha_3.ark, alpha_17.ark);
+    }
+
+    #[test]
+    fn test_secure_cauchy_mds_is_invertible() {
+        let mds = super::secure_cauchy_mds::<TestFr>(3);
+        let inv = crate::poseidon::invert_matrix(&mds);
+        let product = crate::poseidon::mat_mul(&mds, &inv);
+        for (i, row) in product.iter().enumerate() {
+            for (j, elem) in row.iter().enumerate() {
+                let expected = if i == j { TestFr::from(1u8) } else { TestFr::from(0u8) };
+                assert_eq!(*elem, expected);
+            }
+        }
+    }
+}
+
+/// Gaussian elimination over `F`, used only to test rank (no need for the numerical
+/// stability a floating-point implementation would require, since `F` arithmetic is
+/// exact).
+fn rows_are_linearly_independent<F: PrimeField>(mut rows: Vec<Vec<F>>) -> bool {
+    let n = rows.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|&r| !rows[r][col].is_zero());
+        let pivot = match pivot {
+            Some(p) => p,
+            None => return false,
+        };
+        rows.swap(col, pivot);
+
+        let inv = rows[col][col].inverse().unwrap();
+        for row in (col + 1)..n {
+            let factor = rows[row][col] * inv;
+            if !factor.is_zero() {
+                for c in col..n {
+                    let term = rows[col][c] * factor;
+                    rows[row][c] -= term;
+                }
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,155 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::vec::Vec;
+
+/// The Grain LFSR used by the Poseidon reference implementation to deterministically
+/// derive round constants from a field and set of Poseidon parameters (the Cauchy-matrix
+/// evaluation points in [`super::generate_parameters`] do not come from it), so that two parties
+/// agreeing on `(rate, capacity, alpha, full_rounds, partial_rounds)` agree on the same
+/// parameters without needing to transmit them.
+///
+/// The 80-bit state is seeded from those parameters and then clocked 160 times (per the
+/// Grain specification) before any output is taken, so that output bits do not trivially
+/// leak the seed.
+#[derive(Debug)]
+pub(crate) struct PoseidonGrainLfsr {
+    state: [bool; 80],
+    head: usize,
+}
+
+impl PoseidonGrainLfsr {
+    pub(crate) fn new(
+        is_sbox_an_inverse: bool,
+        prime_num_bits: u64,
+        state_len: u64,
+        num_full_rounds: u64,
+        num_partial_rounds: u64,
+        alpha: u64,
+    ) -> Self {
+        let mut state = [false; 80];
+
+        // b0, b1: 2 bits, always 1 (field type = prime field)
+        state[0] = true;
+        state[1] = true;
+        // b2..=b13: 12 bits, field size in bits
+        for i in 0..12 {
+            state[2 + i] = (prime_num_bits >> (11 - i)) & 1 == 1;
+        }
+        // b14..=b25: 12 bits, state size (t = rate + capacity)
+        for i in 0..12 {
+            state[14 + i] = (state_len >> (11 - i)) & 1 == 1;
+        }
+        // b26..=b35: 10 bits, number of full rounds
+        for i in 0..10 {
+            state[26 + i] = (num_full_rounds >> (9 - i)) & 1 == 1;
+        }
+        // b36..=b45: 10 bits, number of partial rounds
+        for i in 0..10 {
+            state[36 + i] = (num_partial_rounds >> (9 - i)) & 1 == 1;
+        }
+        // b46..=b61: 16 bits, the S-box exponent (for SBox::Power; 0 for SBox::Inverse)
+        for i in 0..16 {
+            state[46 + i] = (alpha >> (15 - i)) & 1 == 1;
+        }
+        // b62..=b78: 17 bits, all ones (padding, per the Grain spec)
+        for i in 0..17 {
+            state[62 + i] = true;
+        }
+        // b79: whether the S-box is the inverse S-box rather than x^alpha
+        state[79] = is_sbox_an_inverse;
+
+        let mut lfsr = Self { state, head: 0 };
+        for _ in 0..160 {
+            lfsr.update();
+        }
+        lfsr
+    }
+
+    fn update(&mut self) -> bool {
+        let new_bit = self.state[self.head]
+            ^ self.state[(self.head + 13) % 80]
+            ^ self.state[(self.head + 23) % 80]
+            ^ self.state[(self.head + 38) % 80]
+            ^ self.state[(self.head + 51) % 80]
+            ^ self.state[(self.head + 62) % 80];
+        self.state[self.head] = new_bit;
+        self.head = (self.head + 1) % 80;
+        new_bit
+    }
+
+    /// Produces `num_bits` output bits using the Grain self-shrinking rule: bits are
+    /// generated from the LFSR in pairs, and a pair is kept (emitting its second bit)
+    /// only if its first bit is `1`; otherwise the pair is discarded and the next pair
+    /// is tried.
+    fn get_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let mut bits = Vec::with_capacity(num_bits);
+        while bits.len() < num_bits {
+            let keep = self.update();
+            let bit = self.update();
+            if keep {
+                bits.push(bit);
+            }
+        }
+        bits
+    }
+
+    /// Draws `num_elems` field elements by reading `F::size_in_bits()` bits at a time
+    /// (big-endian) and rejecting any candidate that is not a canonical representative,
+    /// i.e. `>= MODULUS`.
+    pub(crate) fn get_field_elements_rejection_sampling<F: PrimeField>(
+        &mut self,
+        num_elems: usize,
+    ) -> Vec<F> {
+        let num_bits = F::size_in_bits();
+        let mut elems = Vec::with_capacity(num_elems);
+        while elems.len() < num_elems {
+            let bits = self.get_bits(num_bits);
+            if let Some(f) = F::from_repr(F::BigInt::from_bits_be(&bits)) {
+                elems.push(f);
+            }
+        }
+        elems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoseidonGrainLfsr;
+    use crate::poseidon::test::TestFr;
+    use ark_ff::PrimeField;
+
+    #[test]
+    fn test_seeding_is_deterministic() {
+        let mut a = PoseidonGrainLfsr::new(false, TestFr::size_in_bits() as u64, 3, 8, 31, 5);
+        let mut b = PoseidonGrainLfsr::new(false, TestFr::size_in_bits() as u64, 3, 8, 31, 5);
+
+        let elems_a = a.get_field_elements_rejection_sampling::<TestFr>(4);
+        let elems_b = b.get_field_elements_rejection_sampling::<TestFr>(4);
+        assert_eq!(elems_a, elems_b);
+    }
+
+    #[test]
+    fn test_field_size_is_not_truncated_to_6_bits() {
+        // MODULUS_BITS for TestFr (BLS12-381's Fr) is 255, which overflows a 6-bit
+        // field-size allocation (max 63): a truncated seed would collide with some
+        // other field whose real size happens to be `255 % 64`, i.e. 63. Seeding with
+        // both should no longer produce the same stream now that the field-size
+        // portion of the seed has enough bits to hold 255 without wrapping.
+        let mut real = PoseidonGrainLfsr::new(false, TestFr::size_in_bits() as u64, 3, 8, 31, 5);
+        let mut truncated = PoseidonGrainLfsr::new(false, 63, 3, 8, 31, 5);
+
+        let real_elems = real.get_field_elements_rejection_sampling::<TestFr>(4);
+        let truncated_elems = truncated.get_field_elements_rejection_sampling::<TestFr>(4);
+        assert_ne!(real_elems, truncated_elems);
+    }
+
+    #[test]
+    fn test_different_alpha_yields_different_seed() {
+        let mut alpha_3 = PoseidonGrainLfsr::new(false, TestFr::size_in_bits() as u64, 3, 8, 31, 3);
+        let mut alpha_17 =
+            PoseidonGrainLfsr::new(false, TestFr::size_in_bits() as u64, 3, 8, 31, 17);
+
+        let elems_3 = alpha_3.get_field_elements_rejection_sampling::<TestFr>(4);
+        let elems_17 = alpha_17.get_field_elements_rejection_sampling::<TestFr>(4);
+        assert_ne!(elems_3, elems_17);
+    }
+}
@@ -0,0 +1,192 @@
+use super::{PoseidonParameters, PoseidonSponge};
+use crate::{CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+use ark_std::marker::PhantomData;
+use ark_std::vec::Vec;
+
+/// A domain separator for a Poseidon-based hash.
+///
+/// A `Domain` fixes how the capacity portion of the sponge is initialized and how an
+/// input message is padded before being absorbed, so that a hash computed under one
+/// domain cannot be confused with a hash of the same elements computed under another
+/// (e.g. a fixed-length hash colliding with a streaming hash of the same elements, or
+/// two differently-padded messages producing the same state).
+pub trait Domain<F: PrimeField> {
+    /// The tag used to initialize the capacity portion of the sponge state. Distinct
+    /// domains (for the same `rate`/`capacity`) must produce distinct tags.
+    fn domain_tag(rate: usize, capacity: usize) -> F;
+
+    /// Pads `input` to a multiple of `rate`, according to this domain's padding rule.
+    fn pad(input: &[F], rate: usize) -> Vec<F>;
+}
+
+/// Packs a domain tag as `is_variable << 63 | length << 32 | rate << 16 | capacity`.
+///
+/// `is_variable` occupies a bit disjoint from `length`'s field, so a `VariableLength`
+/// tag can never alias a `ConstantLength<L>` tag for any `L` (unlike encoding "variable"
+/// as just another length value, which collides with `ConstantLength<1>`). `rate` and
+/// `capacity` each get their own 16-bit field so that hashers differing only in one of
+/// them (for the same domain and length) never tag-collide either. Panics if `length`,
+/// `rate`, or `capacity` overflow their field, rather than silently folding into the
+/// adjacent field and risking exactly the kind of collision this tag exists to prevent.
+fn domain_tag<F: PrimeField>(is_variable: bool, length: u64, rate: usize, capacity: usize) -> F {
+    assert!(length < 1 << 31, "domain length {} exceeds 31 bits", length);
+    assert!(rate < 1 << 16, "rate {} exceeds 16 bits", rate);
+    assert!(capacity < 1 << 16, "capacity {} exceeds 16 bits", capacity);
+    F::from((is_variable as u64) << 63 | length << 32 | (rate as u64) << 16 | capacity as u64)
+}
+
+/// A domain for hashing a message of a fixed, statically-known length `L`.
+///
+/// Since the length is fixed and encoded in the domain tag, padding to a multiple of
+/// `rate` is simply zeros: there is no ambiguity between a message and its zero-padded
+/// extension, because that extension would be hashed under the domain for a different
+/// `L`.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstantLength<const L: usize>;
+
+impl<F: PrimeField, const L: usize> Domain<F> for ConstantLength<L> {
+    fn domain_tag(rate: usize, capacity: usize) -> F {
+        // Bit 63 is a dedicated "is variable-length" flag (0 here), so this can never
+        // alias a `VariableLength` tag regardless of `L`; `ConstantLength<L'>` for
+        // `L' != L` is distinguished by the length field itself.
+        domain_tag(false, L as u64, rate, capacity)
+    }
+
+    fn pad(input: &[F], rate: usize) -> Vec<F> {
+        assert_eq!(
+            input.len(),
+            L,
+            "ConstantLength<{}> requires exactly {} input elements, got {}",
+            L,
+            L,
+            input.len()
+        );
+        let mut padded = input.to_vec();
+        let remainder = padded.len() % rate;
+        if remainder != 0 {
+            padded.resize(padded.len() + (rate - remainder), F::zero());
+        }
+        padded
+    }
+}
+
+/// A domain for hashing a message whose length is not statically known.
+///
+/// A `1` marker is appended immediately after the message, before zero-padding to a
+/// multiple of `rate`. Unlike [`ConstantLength`], this is required here because the
+/// length is not fixed by the domain tag alone: without the marker, messages differing
+/// only by a trailing run of zero elements would absorb identically.
+#[derive(Clone, Copy, Debug)]
+pub struct VariableLength;
+
+impl<F: PrimeField> Domain<F> for VariableLength {
+    fn domain_tag(rate: usize, capacity: usize) -> F {
+        // Bit 63 is the dedicated "is variable-length" flag (1 here); the length field
+        // is unused (0) since `VariableLength` has no static `L`.
+        domain_tag(true, 0, rate, capacity)
+    }
+
+    fn pad(input: &[F], rate: usize) -> Vec<F> {
+        let mut padded = input.to_vec();
+        padded.push(F::one());
+        let remainder = padded.len() % rate;
+        if remainder != 0 {
+            padded.resize(padded.len() + (rate - remainder), F::zero());
+        }
+        padded
+    }
+}
+
+/// A one-shot, domain-separated Poseidon hash.
+///
+/// Unlike driving a [`PoseidonSponge`] directly through [`CryptographicSponge::absorb`]
+/// and `squeeze_*`, `PoseidonHasher` initializes the sponge's capacity with `D`'s domain
+/// tag and pads the message with `D`'s padding rule before absorbing it, giving an
+/// unambiguous, collision-resistant encoding of the input.
+pub struct PoseidonHasher<F: PrimeField, D: Domain<F>> {
+    parameters: PoseidonParameters<F>,
+    domain: PhantomData<D>,
+}
+
+impl<F: PrimeField, D: Domain<F>> PoseidonHasher<F, D> {
+    /// Creates a hasher that will use `parameters` for its underlying permutation.
+    pub fn new(parameters: PoseidonParameters<F>) -> Self {
+        Self {
+            parameters,
+            domain: PhantomData,
+        }
+    }
+
+    fn domain_separated_sponge(&self) -> PoseidonSponge<F> {
+        let mut sponge = PoseidonSponge::new(&self.parameters);
+        sponge.state[0] = D::domain_tag(self.parameters.rate, self.parameters.capacity);
+        sponge
+    }
+
+    /// Pads and absorbs `input` in one shot, then squeezes a single field element.
+    pub fn hash(&self, input: &[F]) -> F {
+        self.hash_to_n(input, 1)[0]
+    }
+
+    /// Pads and absorbs `input` in one shot, then squeezes `n` field elements.
+    pub fn hash_to_n(&self, input: &[F], n: usize) -> Vec<F> {
+        let mut sponge = self.domain_separated_sponge();
+        sponge.absorb(&D::pad(input, self.parameters.rate));
+        sponge.squeeze_native_field_elements(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstantLength, Domain, PoseidonHasher, VariableLength};
+    use crate::poseidon::test::TestFr;
+    use crate::poseidon::{PoseidonDefaultParameters, PoseidonDefaultParametersField};
+
+    #[test]
+    fn test_constant_length_hash_is_deterministic_and_input_sensitive() {
+        let params = TestFr::get_default_poseidon_parameters(3, false).unwrap();
+        let hasher = PoseidonHasher::<TestFr, ConstantLength<2>>::new(params);
+
+        let input = vec![TestFr::from(1u8), TestFr::from(2u8)];
+        assert_eq!(hasher.hash(&input), hasher.hash(&input));
+
+        let other_input = vec![TestFr::from(1u8), TestFr::from(3u8)];
+        assert_ne!(hasher.hash(&input), hasher.hash(&other_input));
+    }
+
+    #[test]
+    fn test_constant_length_and_variable_length_do_not_collide() {
+        let params = TestFr::get_default_poseidon_parameters(3, false).unwrap();
+        let input = vec![TestFr::from(1u8), TestFr::from(2u8)];
+
+        let constant_hasher = PoseidonHasher::<TestFr, ConstantLength<2>>::new(params.clone());
+        let variable_hasher = PoseidonHasher::<TestFr, VariableLength>::new(params);
+
+        assert_ne!(constant_hasher.hash(&input), variable_hasher.hash(&input));
+    }
+
+    #[test]
+    fn test_domain_tags_never_collide() {
+        assert_ne!(
+            <ConstantLength<1> as Domain<TestFr>>::domain_tag(3, 1),
+            <VariableLength as Domain<TestFr>>::domain_tag(3, 1)
+        );
+        assert_ne!(
+            <ConstantLength<0> as Domain<TestFr>>::domain_tag(3, 1),
+            <VariableLength as Domain<TestFr>>::domain_tag(3, 1)
+        );
+    }
+
+    #[test]
+    fn test_domain_tag_depends_on_capacity() {
+        assert_ne!(
+            <ConstantLength<2> as Domain<TestFr>>::domain_tag(3, 1),
+            <ConstantLength<2> as Domain<TestFr>>::domain_tag(3, 2)
+        );
+        assert_ne!(
+            <VariableLength as Domain<TestFr>>::domain_tag(3, 1),
+            <VariableLength as Domain<TestFr>>::domain_tag(3, 2)
+        );
+    }
+}
@@ -17,8 +17,19 @@ mod tests;
 pub mod traits;
 pub use traits::*;
 
+/// domain-separated, padded one-shot hashing on top of `PoseidonSponge`
+pub mod hash;
+pub use hash::*;
+
+/// type-state sponge API that forbids absorbing after squeezing
+pub mod typed;
+pub use typed::{Absorbing, Sponge, Squeezing};
+
 mod grain_lfsr;
 
+mod generate;
+pub use generate::generate_parameters;
+
 /// Parameters and RNG used
 #[derive(Clone, Debug)]
 pub struct PoseidonParameters<F: PrimeField> {
@@ -26,8 +37,8 @@ pub struct PoseidonParameters<F: PrimeField> {
     pub full_rounds: usize,
     /// Number of rounds in a partial-round operation.
     pub partial_rounds: usize,
-    /// Exponent used in S-boxes.
-    pub alpha: u64,
+    /// The S-box applied in each round.
+    pub sbox: SBox,
     /// Additive Round keys. These are added before each MDS matrix application to make it an affine shift.
     /// They are indexed by `ark[round_num][state_element_index]`
     pub ark: Vec<Vec<F>>,
@@ -39,6 +50,223 @@ pub struct PoseidonParameters<F: PrimeField> {
     pub rate: usize,
     /// The capacity (in terms of number of field elements).
     pub capacity: usize,
+    /// Precomputed factorization of the partial-round MDS applications.
+    ///
+    /// When present, [`PoseidonSponge::permute`] replaces the `partial_rounds` dense
+    /// `mds` multiplications (each `O(t^2)`) with a single dense multiplication
+    /// followed by one sparse multiplication per partial round (each `O(t)`), producing
+    /// bit-identical output to the unoptimized path.
+    pub optimized_mds: Option<OptimizedMdsMatrices<F>>,
+}
+
+/// The S-box a [`PoseidonParameters`] applies in each round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SBox {
+    /// `x -> x^alpha`, for some `alpha` with `gcd(alpha, p - 1) = 1`.
+    Power(u64),
+    /// `x -> x^{-1}`, with `0` mapped to `0`. Used by Poseidon instances over fields
+    /// where no small `alpha` is coprime to `p - 1`.
+    Inverse,
+}
+
+/// A sparse matrix equivalent, for a single partial round, to multiplying the state by
+/// the dense MDS matrix.
+///
+/// The matrix this represents is the identity in its lower-right `(t-1)x(t-1)` block,
+/// with `w_00` in the top-left corner, `w_hat` as the rest of the first row, and `v` as
+/// the rest of the first column. Multiplying a width-`t` state by it costs `2t-1`
+/// multiplications instead of the `t^2` a dense matrix-vector product would cost.
+#[derive(Clone, Debug)]
+pub struct SparseMdsMatrix<F: PrimeField> {
+    /// `M[0][0]` of the matrix this factor replaces.
+    pub w_00: F,
+    /// The rest of the first row, `M[0][1..]`.
+    pub w_hat: Vec<F>,
+    /// The rest of the first column, `M[1..][0]`.
+    pub v: Vec<F>,
+}
+
+impl<F: PrimeField> SparseMdsMatrix<F> {
+    fn apply(&self, state: &mut [F]) {
+        let t = state.len();
+        let mut new_state = vec![F::zero(); t];
+
+        let mut first = self.w_00 * state[0];
+        for j in 1..t {
+            first += self.w_hat[j - 1] * state[j];
+        }
+        new_state[0] = first;
+
+        for i in 1..t {
+            new_state[i] = self.v[i - 1] * state[0] + state[i];
+        }
+
+        state.clone_from_slice(&new_state);
+    }
+}
+
+/// The optimized partial-round linear-layer factorization computed by
+/// [`PoseidonParameters::with_optimized_mds`].
+#[derive(Clone, Debug)]
+pub struct OptimizedMdsMatrices<F: PrimeField> {
+    /// The single dense matrix applied once, immediately before the partial-round block.
+    pub pre_sparse_mds: Vec<Vec<F>>,
+    /// One sparse matrix per partial round, applied in round order.
+    pub sparse_matrices: Vec<SparseMdsMatrix<F>>,
+    /// The partial rounds' round constants, adjusted to account for `pre_sparse_mds`
+    /// and the `sparse_matrices` substitution (see [`factorize_partial_round_linear_layer`]);
+    /// used instead of the corresponding rows of [`PoseidonParameters::ark`] when this
+    /// optimization is active. Only the "rest" coordinates (index `1..`) actually
+    /// differ from the original constants; the S-box coordinate (index `0`) is
+    /// unaffected and carried over unchanged.
+    pub partial_round_ark: Vec<Vec<F>>,
+}
+
+/// Factors `partial_round_ark.len()` repeated applications of the partial-round linear
+/// layer (`ark`, S-box on coordinate `0`, multiply by `mds`) into a single dense matrix
+/// applied once up front plus one sparse matrix per round, with the partial rounds'
+/// round constants adjusted to match.
+///
+/// Writing `mds = [[w_00, w^T], [v, M_hat]]` (splitting off the first row/column), a
+/// single partial round maps `(s, rest)` -- with `s` already post-S-box -- to
+/// `(w_00*s + w.rest, v*s + M_hat*rest)`. Substituting, for the `i`-th round (1-indexed)
+/// of `R` total, the "rest" coordinates with `u_i = M_hat^(R-i+1) * rest_i` turns every
+/// `M_hat` multiplication into the identity (absorbed into the substitution itself),
+/// leaving a sparse per-round update; the substitution requires one `M_hat^R`
+/// multiplication up front (`pre_sparse_mds`) to convert the real incoming state into
+/// the round-1 representation, and replaces round `i`'s "rest" round constant by
+/// `M_hat^(R-i+1)` times the original (its S-box-coordinate constant is untouched,
+/// since that coordinate is never substituted). By the end of round `R` the
+/// substitution's power of `M_hat` has reached `0`, so the state is back in its real
+/// representation with no final correction needed.
+fn factorize_partial_round_linear_layer<F: PrimeField>(
+    mds: &[Vec<F>],
+    partial_round_ark: &[Vec<F>],
+) -> OptimizedMdsMatrices<F> {
+    let t = mds.len();
+    let r = partial_round_ark.len();
+
+    let w_00 = mds[0][0];
+    let w: Vec<F> = mds[0][1..].to_vec();
+    let v: Vec<F> = (1..t).map(|i| mds[i][0]).collect();
+    let m_hat: Vec<Vec<F>> = (1..t).map(|i| mds[i][1..].to_vec()).collect();
+    let m_hat_inv_t = transpose(&invert_matrix(&m_hat));
+
+    // `pow` tracks `M_hat^j`, `w_vec` tracks `(M_hat^{-1})^{j-transpose} * w`, and
+    // `v_vec` tracks `M_hat^{j-1} * v`, where `j` is the number of iterations completed
+    // so far (rounds are processed from the last partial round back to the first, as
+    // `j` runs `1..=R`).
+    let mut pow = identity_matrix::<F>(t - 1);
+    let mut w_vec = w;
+    let mut v_vec = v;
+
+    let mut sparse_matrices = Vec::with_capacity(r);
+    let mut partial_round_ark_rev = Vec::with_capacity(r);
+    for ark in partial_round_ark.iter().rev() {
+        w_vec = mat_vec_mul(&m_hat_inv_t, &w_vec);
+        pow = mat_mul(&m_hat, &pow);
+
+        sparse_matrices.push(SparseMdsMatrix {
+            w_00,
+            w_hat: w_vec.clone(),
+            v: v_vec.clone(),
+        });
+
+        let mut adjusted = Vec::with_capacity(t);
+        adjusted.push(ark[0]);
+        adjusted.extend(mat_vec_mul(&pow, &ark[1..]));
+        partial_round_ark_rev.push(adjusted);
+
+        v_vec = mat_vec_mul(&m_hat, &v_vec);
+    }
+    sparse_matrices.reverse();
+    partial_round_ark_rev.reverse();
+
+    let mut pre_sparse_mds = vec![vec![F::zero(); t]; t];
+    pre_sparse_mds[0][0] = F::one();
+    for i in 1..t {
+        for j in 1..t {
+            pre_sparse_mds[i][j] = pow[i - 1][j - 1];
+        }
+    }
+
+    OptimizedMdsMatrices {
+        pre_sparse_mds,
+        sparse_matrices,
+        partial_round_ark: partial_round_ark_rev,
+    }
+}
+
+fn identity_matrix<F: PrimeField>(n: usize) -> Vec<Vec<F>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { F::one() } else { F::zero() }).collect())
+        .collect()
+}
+
+fn transpose<F: PrimeField>(m: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = m.len();
+    (0..n).map(|j| (0..n).map(|i| m[i][j]).collect()).collect()
+}
+
+fn mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = a.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).fold(F::zero(), |acc, k| acc + a[i][k] * b[k][j]))
+                .collect()
+        })
+        .collect()
+}
+
+fn mat_vec_mul<F: PrimeField>(m: &[Vec<F>], v: &[F]) -> Vec<F> {
+    m.iter()
+        .map(|row| {
+            row.iter()
+                .zip(v)
+                .fold(F::zero(), |acc, (m_ij, v_j)| acc + *m_ij * *v_j)
+        })
+        .collect()
+}
+
+/// Inverts a square matrix over `F` via Gauss-Jordan elimination. Panics if `m` is
+/// singular. Every matrix this crate inverts is a minor of an MDS matrix, which by
+/// definition has only invertible square submatrices.
+fn invert_matrix<F: PrimeField>(m: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = m.len();
+    let mut a = m.to_vec();
+    let mut inv = identity_matrix::<F>(n);
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .find(|&r| !a[r][col].is_zero())
+            .expect("matrix is singular");
+        a.swap(col, pivot_row);
+        inv.swap(col, pivot_row);
+
+        let inv_pivot = a[col][col].inverse().expect("pivot is non-zero");
+        for c in 0..n {
+            a[col][c] *= inv_pivot;
+            inv[col][c] *= inv_pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            if !factor.is_zero() {
+                for c in 0..n {
+                    let term_a = a[col][c] * factor;
+                    a[row][c] -= term_a;
+                    let term_inv = inv[col][c] * factor;
+                    inv[row][c] -= term_inv;
+                }
+            }
+        }
+    }
+
+    inv
 }
 
 #[derive(Clone)]
@@ -60,60 +288,19 @@ pub struct PoseidonSponge<F: PrimeField> {
 }
 
 impl<F: PrimeField> PoseidonSponge<F> {
-    fn apply_s_box(&self, state: &mut [F], is_full_round: bool) {
-        // Full rounds apply the S Box (x^alpha) to every element of state
-        if is_full_round {
-            for elem in state {
-                *elem = elem.pow(&[self.parameters.alpha]);
-            }
-        }
-        // Partial rounds apply the S Box (x^alpha) to just the first element of state
-        else {
-            state[0] = state[0].pow(&[self.parameters.alpha]);
-        }
-    }
-
-    fn apply_ark(&self, state: &mut [F], round_number: usize) {
-        for (i, state_elem) in state.iter_mut().enumerate() {
-            state_elem.add_assign(&self.parameters.ark[round_number][i]);
-        }
-    }
-
-    fn apply_mds(&self, state: &mut [F]) {
-        let mut new_state = Vec::new();
-        for i in 0..state.len() {
-            let mut cur = F::zero();
-            for (j, state_elem) in state.iter().enumerate() {
-                let term = state_elem.mul(&self.parameters.mds[i][j]);
-                cur.add_assign(&term);
-            }
-            new_state.push(cur);
-        }
-        state.clone_from_slice(&new_state[..state.len()])
+    /// Returns the sponge to its freshly-constructed state: a zeroed state and
+    /// `Absorbing { next_absorb_index: 0 }`. This lets a parameterized sponge be reused
+    /// across many independent hashes without reallocating its parameters.
+    pub fn reset(&mut self) {
+        self.state = vec![F::zero(); self.parameters.rate + self.parameters.capacity];
+        self.mode = DuplexSpongeMode::Absorbing {
+            next_absorb_index: 0,
+        };
     }
 
     fn permute(&mut self) {
-        let full_rounds_over_2 = self.parameters.full_rounds / 2;
         let mut state = self.state.clone();
-        for i in 0..full_rounds_over_2 {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, true);
-            self.apply_mds(&mut state);
-        }
-
-        for i in full_rounds_over_2..(full_rounds_over_2 + self.parameters.partial_rounds) {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, false);
-            self.apply_mds(&mut state);
-        }
-
-        for i in (full_rounds_over_2 + self.parameters.partial_rounds)
-            ..(self.parameters.partial_rounds + self.parameters.full_rounds)
-        {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, true);
-            self.apply_mds(&mut state);
-        }
+        self.parameters.permute(&mut state);
         self.state = state;
     }
 
@@ -182,8 +369,20 @@ impl<F: PrimeField> PoseidonSponge<F> {
     }
 }
 
+fn apply_dense_mds<F: PrimeField>(mds: &[Vec<F>], state: &mut [F]) {
+    let mut new_state = Vec::new();
+    for row in mds.iter().take(state.len()) {
+        let mut cur = F::zero();
+        for (state_elem, mds_elem) in state.iter().zip(row) {
+            cur.add_assign(&state_elem.mul(mds_elem));
+        }
+        new_state.push(cur);
+    }
+    state.clone_from_slice(&new_state[..state.len()])
+}
+
 impl<F: PrimeField> PoseidonParameters<F> {
-    /// Initialize the parameter for Poseidon Sponge.
+    /// Initialize the parameters for a Poseidon Sponge using the `x -> x^alpha` S-box.
     pub fn new(
         full_rounds: usize,
         partial_rounds: usize,
@@ -192,6 +391,28 @@ impl<F: PrimeField> PoseidonParameters<F> {
         ark: Vec<Vec<F>>,
         rate: usize,
         capacity: usize,
+    ) -> Self {
+        Self::new_with_sbox(
+            full_rounds,
+            partial_rounds,
+            SBox::Power(alpha),
+            mds,
+            ark,
+            rate,
+            capacity,
+        )
+    }
+
+    /// Initialize the parameters for a Poseidon Sponge using an arbitrary [`SBox`],
+    /// e.g. [`SBox::Inverse`] for fields where no small `alpha` is coprime to `p - 1`.
+    pub fn new_with_sbox(
+        full_rounds: usize,
+        partial_rounds: usize,
+        sbox: SBox,
+        mds: Vec<Vec<F>>,
+        ark: Vec<Vec<F>>,
+        rate: usize,
+        capacity: usize,
     ) -> Self {
         assert_eq!(ark.len(), full_rounds + partial_rounds);
         for item in &ark {
@@ -204,11 +425,103 @@ impl<F: PrimeField> PoseidonParameters<F> {
         Self {
             full_rounds,
             partial_rounds,
-            alpha,
+            sbox,
             mds,
             ark,
             rate,
             capacity,
+            optimized_mds: None,
+        }
+    }
+
+    /// Precomputes the sparse-MDS factorization of the partial-round linear layers
+    /// (see [`OptimizedMdsMatrices`]) and stores it so that [`PoseidonParameters::permute`]
+    /// can use it instead of the dense `mds` matrix. The resulting hashes are
+    /// bit-identical to the unoptimized path; only the number of field multiplications
+    /// performed during the partial rounds changes.
+    pub fn with_optimized_mds(mut self) -> Self {
+        let full_rounds_over_2 = self.full_rounds / 2;
+        let partial_round_ark =
+            &self.ark[full_rounds_over_2..(full_rounds_over_2 + self.partial_rounds)];
+        self.optimized_mds = Some(factorize_partial_round_linear_layer(
+            &self.mds,
+            partial_round_ark,
+        ));
+        self
+    }
+
+    fn apply_ark_values(&self, state: &mut [F], values: &[F]) {
+        for (state_elem, value) in state.iter_mut().zip(values) {
+            state_elem.add_assign(value);
+        }
+    }
+
+    fn apply_s_box(&self, state: &mut [F], is_full_round: bool) {
+        let apply_one = |elem: &F| -> F {
+            match self.sbox {
+                SBox::Power(alpha) => elem.pow(&[alpha]),
+                SBox::Inverse => elem.inverse().unwrap_or_else(F::zero),
+            }
+        };
+
+        // Full rounds apply the S-box to every element of state
+        if is_full_round {
+            for elem in state {
+                *elem = apply_one(elem);
+            }
+        }
+        // Partial rounds apply the S-box to just the first element of state
+        else {
+            state[0] = apply_one(&state[0]);
+        }
+    }
+
+    fn apply_ark(&self, state: &mut [F], round_number: usize) {
+        self.apply_ark_values(state, &self.ark[round_number]);
+    }
+
+    /// Applies the Poseidon permutation in place to a caller-provided width-`t`
+    /// (`t = rate + capacity`) state, with no sponge bookkeeping.
+    ///
+    /// Exposed so that downstream users who need the raw fixed-width permutation --
+    /// to build their own sponge/duplex mode, a block-cipher-style construction, or a
+    /// Merkle compression function -- don't have to reconstruct it from
+    /// [`PoseidonSponge`]'s internals.
+    pub fn permute(&self, state: &mut [F]) {
+        let full_rounds_over_2 = self.full_rounds / 2;
+        for i in 0..full_rounds_over_2 {
+            self.apply_ark(state, i);
+            self.apply_s_box(state, true);
+            apply_dense_mds(&self.mds, state);
+        }
+
+        match &self.optimized_mds {
+            Some(optimized) => {
+                apply_dense_mds(&optimized.pre_sparse_mds, state);
+                for (sparse, ark) in optimized
+                    .sparse_matrices
+                    .iter()
+                    .zip(optimized.partial_round_ark.iter())
+                {
+                    self.apply_ark_values(state, ark);
+                    self.apply_s_box(state, false);
+                    sparse.apply(state);
+                }
+            }
+            None => {
+                for i in full_rounds_over_2..(full_rounds_over_2 + self.partial_rounds) {
+                    self.apply_ark(state, i);
+                    self.apply_s_box(state, false);
+                    apply_dense_mds(&self.mds, state);
+                }
+            }
+        }
+
+        for i in (full_rounds_over_2 + self.partial_rounds)..(self.partial_rounds + self.full_rounds)
+        {
+            self.apply_ark(state, i);
+            self.apply_s_box(state, true);
+            apply_dense_mds(&self.mds, state);
         }
     }
 }
@@ -371,8 +684,13 @@ mod test {
     use crate::poseidon::{
         PoseidonDefaultParameters, PoseidonDefaultParametersEntry, PoseidonDefaultParametersField,
     };
-    use crate::{poseidon::PoseidonSponge, CryptographicSponge, FieldBasedCryptographicSponge};
-    use ark_ff::{field_new, BigInteger256, FftParameters, Fp256, Fp256Parameters, FpParameters};
+    use crate::{
+        poseidon::{PoseidonSponge, SBox},
+        CryptographicSponge, FieldBasedCryptographicSponge,
+    };
+    use ark_ff::{
+        field_new, BigInteger256, FftParameters, Field, Fp256, Fp256Parameters, FpParameters,
+    };
     use ark_test_curves::bls12_381::FrParameters;
 
     pub struct TestFrParameters;
@@ -456,4 +774,74 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_poseidon_sponge_optimized_mds_matches_unoptimized() {
+        let sponge_param = TestFr::get_default_poseidon_parameters(2, false).unwrap();
+        let optimized_param = sponge_param.clone().with_optimized_mds();
+
+        let input = vec![TestFr::from(0u8), TestFr::from(1u8), TestFr::from(2u8)];
+
+        let mut sponge = PoseidonSponge::<TestFr>::new(&sponge_param);
+        sponge.absorb(&input);
+        let expected = sponge.squeeze_native_field_elements(3);
+
+        let mut optimized_sponge = PoseidonSponge::<TestFr>::new(&optimized_param);
+        optimized_sponge.absorb(&input);
+        let actual = optimized_sponge.squeeze_native_field_elements(3);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sbox_inverse_maps_zero_to_zero_and_inverts_elsewhere() {
+        let mut sponge_param = TestFr::get_default_poseidon_parameters(2, false).unwrap();
+        sponge_param.sbox = SBox::Inverse;
+
+        let mut state = vec![TestFr::from(0u8), TestFr::from(5u8), TestFr::from(7u8)];
+        sponge_param.apply_s_box(&mut state, true);
+
+        assert_eq!(state[0], TestFr::from(0u8));
+        assert_eq!(state[1], TestFr::from(5u8).inverse().unwrap());
+        assert_eq!(state[2], TestFr::from(7u8).inverse().unwrap());
+    }
+
+    #[test]
+    fn test_reset_reproduces_a_fresh_sponge() {
+        let sponge_param = TestFr::get_default_poseidon_parameters(2, false).unwrap();
+
+        let mut sponge = PoseidonSponge::<TestFr>::new(&sponge_param);
+        sponge.absorb(&vec![TestFr::from(0u8), TestFr::from(1u8), TestFr::from(2u8)]);
+        let _ = sponge.squeeze_native_field_elements(2);
+        sponge.reset();
+
+        let mut fresh = PoseidonSponge::<TestFr>::new(&sponge_param);
+
+        assert_eq!(sponge.state, fresh.state);
+        let input = vec![TestFr::from(3u8), TestFr::from(4u8), TestFr::from(5u8)];
+        sponge.absorb(&input);
+        fresh.absorb(&input);
+        assert_eq!(
+            sponge.squeeze_native_field_elements(3),
+            fresh.squeeze_native_field_elements(3)
+        );
+    }
+
+    #[test]
+    fn test_permute_on_a_caller_supplied_state_matches_the_sponge() {
+        let sponge_param = TestFr::get_default_poseidon_parameters(2, false).unwrap();
+
+        let mut sponge = PoseidonSponge::<TestFr>::new(&sponge_param);
+        sponge.absorb(&vec![TestFr::from(0u8), TestFr::from(1u8), TestFr::from(2u8)]);
+
+        // `PoseidonSponge` permutes its own state internally via a private method that
+        // clones the state, calls `PoseidonParameters::permute`, and writes the result
+        // back; check that calling the public `permute` directly on a copy of the
+        // sponge's current state agrees with that internal path.
+        let mut state = sponge.state.clone();
+        sponge_param.permute(&mut state);
+        sponge.permute();
+
+        assert_eq!(state, sponge.state);
+    }
 }
@@ -0,0 +1,99 @@
+use super::{PoseidonParameters, PoseidonSponge};
+use crate::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+use ark_std::marker::PhantomData;
+use ark_std::vec::Vec;
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for super::Absorbing {}
+    impl Sealed for super::Squeezing {}
+}
+
+/// A state a [`Sponge`] can be in. Sealed: only [`Absorbing`] and [`Squeezing`]
+/// implement it.
+pub trait SpongeState: private::Sealed {}
+
+/// The sponge is accepting input via [`Sponge::absorb`].
+pub struct Absorbing;
+/// The sponge has squeezed at least once and no longer accepts input.
+pub struct Squeezing;
+
+impl SpongeState for Absorbing {}
+impl SpongeState for Squeezing {}
+
+/// A type-state wrapper around [`PoseidonSponge`] that enforces, at compile time, the
+/// sponge discipline "absorb arbitrarily many times, then squeeze arbitrarily many
+/// times, never absorb again": `absorb` is only callable on `Sponge<F, Absorbing>`, and
+/// the first squeeze consumes the sponge into a `Sponge<F, Squeezing>` from which
+/// `absorb` is no longer reachable, so interleaving absorb and squeeze is a compile
+/// error instead of a silent, transcript-breaking permutation.
+///
+/// This sits alongside the untyped [`CryptographicSponge`] impl on `PoseidonSponge`,
+/// which remains available for callers who need to interleave absorb and squeeze.
+pub struct Sponge<F: PrimeField, S: SpongeState> {
+    inner: PoseidonSponge<F>,
+    state: PhantomData<S>,
+}
+
+impl<F: PrimeField> Sponge<F, Absorbing> {
+    /// Creates a new sponge, ready to absorb.
+    pub fn new(parameters: &PoseidonParameters<F>) -> Self {
+        Self {
+            inner: PoseidonSponge::new(parameters),
+            state: PhantomData,
+        }
+    }
+
+    /// Absorbs `input`. May be called any number of times before the first squeeze.
+    pub fn absorb(&mut self, input: &impl Absorb) {
+        self.inner.absorb(input);
+    }
+
+    /// Squeezes `num_elements` field elements, consuming the sponge into its
+    /// [`Squeezing`] state so that it can be squeezed further but never absorbed again.
+    pub fn squeeze_native_field_elements(
+        self,
+        num_elements: usize,
+    ) -> (Vec<F>, Sponge<F, Squeezing>) {
+        let mut squeezing = Sponge {
+            inner: self.inner,
+            state: PhantomData,
+        };
+        let squeezed = squeezing.inner.squeeze_native_field_elements(num_elements);
+        (squeezed, squeezing)
+    }
+}
+
+impl<F: PrimeField> Sponge<F, Squeezing> {
+    /// Squeezes `num_elements` field elements. May be called any number of times.
+    pub fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        self.inner.squeeze_native_field_elements(num_elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Absorbing, Sponge};
+    use crate::poseidon::test::TestFr;
+    use crate::poseidon::{PoseidonDefaultParameters, PoseidonDefaultParametersField};
+    use crate::{poseidon::PoseidonSponge, CryptographicSponge, FieldBasedCryptographicSponge};
+
+    #[test]
+    fn test_typed_sponge_matches_untyped_sponge() {
+        let params = TestFr::get_default_poseidon_parameters(2, false).unwrap();
+        let input = vec![TestFr::from(0u8), TestFr::from(1u8), TestFr::from(2u8)];
+
+        let mut untyped = PoseidonSponge::<TestFr>::new(&params);
+        untyped.absorb(&input);
+        let expected = untyped.squeeze_native_field_elements(3);
+
+        let mut absorbing = Sponge::<TestFr, Absorbing>::new(&params);
+        absorbing.absorb(&input);
+        let (first, mut squeezing) = absorbing.squeeze_native_field_elements(2);
+        let second = squeezing.squeeze_native_field_elements(1);
+
+        assert_eq!(first, expected[..2]);
+        assert_eq!(second, expected[2..]);
+    }
+}